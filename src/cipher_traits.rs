@@ -0,0 +1,86 @@
+/*!
+Implements the [RustCrypto `cipher`](https://docs.rs/cipher) traits for [`XTEA`](crate::XTEA), the
+way [`rc5`](https://docs.rs/rc5) does for its cipher, so `XTEA` can be dropped into the wider
+RustCrypto ecosystem: generic mode wrappers (`cbc`, `ctr`, `ecb`), AEAD constructions, KDFs, etc.
+
+This crate otherwise lets callers pick their own [`ByteOrder`](byteorder::ByteOrder); the `cipher`
+traits have no such parameter, so these impls fix it to `BigEndian`, the conventional byte order
+for XTEA test vectors.
+*/
+
+use byteorder::{BigEndian, ByteOrder};
+use cipher::{consts::U16, generic_array::GenericArray, BlockCipher, KeyInit, KeySizeUser};
+
+use crate::XTEA;
+
+impl BlockCipher for XTEA {}
+
+impl KeySizeUser for XTEA {
+	type KeySize = U16;
+}
+
+impl KeyInit for XTEA {
+	fn new(key: &GenericArray<u8, U16>) -> Self {
+		let key = [
+			BigEndian::read_u32(&key[0..4]),
+			BigEndian::read_u32(&key[4..8]),
+			BigEndian::read_u32(&key[8..12]),
+			BigEndian::read_u32(&key[12..16]),
+		];
+		XTEA::new(key)
+	}
+}
+
+cipher::impl_simple_block_encdec!(
+	XTEA, cipher::consts::U8, cipher, block,
+	encrypt: {
+		let input = [BigEndian::read_u32(&block.get_in()[0..4]), BigEndian::read_u32(&block.get_in()[4..8])];
+		let mut output = [0u32; 2];
+		cipher.encipher(&input, &mut output);
+		BigEndian::write_u32(&mut block.get_out()[0..4], output[0]);
+		BigEndian::write_u32(&mut block.get_out()[4..8], output[1]);
+	}
+	decrypt: {
+		let input = [BigEndian::read_u32(&block.get_in()[0..4]), BigEndian::read_u32(&block.get_in()[4..8])];
+		let mut output = [0u32; 2];
+		cipher.decipher(&input, &mut output);
+		BigEndian::write_u32(&mut block.get_out()[0..4], output[0]);
+		BigEndian::write_u32(&mut block.get_out()[4..8], output[1]);
+	}
+);
+
+#[cfg(test)]
+mod tests {
+	use cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+
+	use super::{BigEndian, ByteOrder, GenericArray, XTEA};
+
+	#[test]
+	fn block_roundtrip() {
+		let key = GenericArray::clone_from_slice(&[0u8; 16]);
+		let xtea = <XTEA as KeyInit>::new(&key);
+
+		let original = *GenericArray::from_slice(b"deadbeef");
+		let mut block = original;
+
+		xtea.encrypt_block(&mut block);
+		assert_ne!(block, original);
+
+		xtea.decrypt_block(&mut block);
+		assert_eq!(block, original);
+	}
+
+	#[test]
+	fn matches_manual_encipher() {
+		let key = GenericArray::clone_from_slice(&[0u8; 16]);
+		let xtea = <XTEA as KeyInit>::new(&key);
+
+		let mut block = *GenericArray::from_slice(b"deadbeef");
+		xtea.encrypt_block(&mut block);
+
+		let mut expected = [0u32; 2];
+		xtea.encipher(&[BigEndian::read_u32(b"dead"), BigEndian::read_u32(b"beef")], &mut expected);
+		assert_eq!(BigEndian::read_u32(&block[0..4]), expected[0]);
+		assert_eq!(BigEndian::read_u32(&block[4..8]), expected[1]);
+	}
+}
@@ -10,6 +10,18 @@ extern crate byteorder;
 use byteorder::{ByteOrder, ReadBytesExt, WriteBytesExt};
 use std::{io::{Read, Result, Write}, io::Cursor, num::Wrapping};
 
+/// Optional integration with the [RustCrypto `cipher`](https://docs.rs/cipher) traits, enabled
+/// with the `cipher` feature.
+#[cfg(feature = "cipher")]
+mod cipher_traits;
+
+/// Chunked authenticated encryption built on top of [`XTEA`]'s CTR mode.
+pub mod aead;
+
+/// Corrected Block TEA (XXTEA), a sibling cipher that mixes an entire variable-length message in
+/// one pass instead of working on independent 64-bit blocks.
+pub mod xxtea;
+
 /// Struct containing the `XTEA` info.
 /// See <https://en.wikipedia.org/wiki/XTEA> for more information
 #[derive(Debug)]
@@ -24,6 +36,20 @@ const DEFAULT_ROUNDS: u32 = 32;
 /// Magic number specified by the algorithm
 const DELTA: Wrapping<u32> = Wrapping(0x9E3779B9);
 
+/// Error returned by [`XTEA::decipher_padded`] when the trailing PKCS#7 padding on a deciphered
+/// message is malformed, e.g. because the ciphertext was corrupted or wasn't produced by
+/// [`XTEA::encipher_padded`] in the first place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaddingError;
+
+impl std::fmt::Display for PaddingError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "invalid PKCS#7 padding")
+	}
+}
+
+impl std::error::Error for PaddingError {}
+
 impl XTEA {
 	/// Creates a new `XTEA` cipher using the given key.
 	#[inline]
@@ -93,6 +119,12 @@ impl XTEA {
 
 	/// Enciphers the given `&[u8]` into the output `&mut [u8]`.
 	///
+	/// This operates in ECB (Electronic Codebook) mode: each 8-byte block is enciphered
+	/// independently, so identical plaintext blocks produce identical ciphertext blocks. If
+	/// that's not acceptable for your use case, see [`encipher_cbc`](XTEA::encipher_cbc),
+	/// [`encipher_cfb`](XTEA::encipher_cfb), [`encipher_ofb`](XTEA::encipher_ofb), or
+	/// [`ctr_apply`](XTEA::ctr_apply) instead.
+	///
 	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
 	///
 	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
@@ -130,6 +162,9 @@ impl XTEA {
 
 	/// Deciphers the given `&[u8]` into the output `&mut [u8]`.
 	///
+	/// This operates in ECB (Electronic Codebook) mode; see the note on
+	/// [`encipher_u8slice`](XTEA::encipher_u8slice) for why that may not be what you want.
+	///
 	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
 	///
 	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
@@ -202,6 +237,236 @@ impl XTEA {
 		*/
 	}
 
+	/// Enciphers the given `&[u8]` into the output `&mut [u8]` using CBC (Cipher Block Chaining) mode.
+	///
+	/// Each plaintext block is XORed with the previous ciphertext block (the given `iv` stands in
+	/// for the "previous" block before the first one) before being enciphered, so identical
+	/// plaintext blocks no longer produce identical ciphertext blocks the way ECB does.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// If the length of the input is not equal to the length of the output.
+	///
+	/// If the length of the input or output is not divisible by 8.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// extern crate xtea;
+	/// extern crate byteorder;
+	///
+	/// use xtea::XTEA;
+	///	use byteorder::BE;
+	///
+	/// let iv = [0u8; 8];
+	/// let input: Box<[u8]> = vec![10u8; 16].into_boxed_slice();
+	///
+	///	let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+	///
+	///	let encrypted = {
+	///		let mut output = vec![0u8; input.len()].into_boxed_slice();
+	///		xtea.encipher_cbc::<BE>(&iv, &input, &mut output);
+	///		output
+	///	};
+	///
+	/// let decrypted = {
+	/// 	let mut output = vec![0u8; input.len()].into_boxed_slice();
+	/// 	xtea.decipher_cbc::<BE>(&iv, &encrypted, &mut output);
+	/// 	output
+	/// };
+	/// assert_eq!(input, decrypted);
+	/// ```
+	///
+	#[inline]
+	pub fn encipher_cbc<B: ByteOrder>(&self, iv: &[u8; 8], input: &[u8], output: &mut [u8]) {
+		assert_eq!(input.len(), output.len(), "The input and output slices must be of the same length.");
+		assert_eq!(input.len() % 8, 0, "Input and output slices must be of a length divisible by 8.");
+
+		let mut feedback = *iv;
+		let mut input_buf = [0u32; 2];
+		let mut output_buf = [0u32; 2];
+		for (in_block, out_block) in input.chunks_exact(8).zip(output.chunks_exact_mut(8)) {
+			let mut xored = [0u8; 8];
+			for i in 0..8 {
+				xored[i] = in_block[i] ^ feedback[i];
+			}
+			input_buf[0] = B::read_u32(&xored[0..4]);
+			input_buf[1] = B::read_u32(&xored[4..8]);
+			self.encipher(&input_buf, &mut output_buf);
+			B::write_u32(&mut out_block[0..4], output_buf[0]);
+			B::write_u32(&mut out_block[4..8], output_buf[1]);
+			feedback.copy_from_slice(out_block);
+		}
+	}
+
+	/// Deciphers the given `&[u8]` into the output `&mut [u8]` using CBC (Cipher Block Chaining) mode.
+	///
+	/// See [`encipher_cbc`](XTEA::encipher_cbc) for an explanation of the mode. The same `iv` used
+	/// to encipher must be supplied here.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// If the length of the input is not equal to the length of the output.
+	///
+	/// If the length of the input or output is not divisible by 8.
+	#[inline]
+	pub fn decipher_cbc<B: ByteOrder>(&self, iv: &[u8; 8], input: &[u8], output: &mut [u8]) {
+		assert_eq!(input.len(), output.len(), "The input and output slices must be of the same length.");
+		assert_eq!(input.len() % 8, 0, "Input and output slices must be of a length divisible by 8.");
+
+		let mut feedback = *iv;
+		let mut input_buf = [0u32; 2];
+		let mut output_buf = [0u32; 2];
+		for (in_block, out_block) in input.chunks_exact(8).zip(output.chunks_exact_mut(8)) {
+			input_buf[0] = B::read_u32(&in_block[0..4]);
+			input_buf[1] = B::read_u32(&in_block[4..8]);
+			self.decipher(&input_buf, &mut output_buf);
+			let mut plain = [0u8; 8];
+			B::write_u32(&mut plain[0..4], output_buf[0]);
+			B::write_u32(&mut plain[4..8], output_buf[1]);
+			for i in 0..8 {
+				out_block[i] = plain[i] ^ feedback[i];
+			}
+			feedback.copy_from_slice(in_block);
+		}
+	}
+
+	/// Enciphers the given `&[u8]` into the output `&mut [u8]` using CFB (Cipher Feedback) mode.
+	///
+	/// CFB only ever calls the forward [`encipher`](XTEA::encipher) on a running feedback
+	/// register, XORing the result into the data; the ciphertext of each block feeds the register
+	/// for the next one, seeded by `iv`.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// If the length of the input is not equal to the length of the output.
+	///
+	/// If the length of the input or output is not divisible by 8.
+	#[inline]
+	pub fn encipher_cfb<B: ByteOrder>(&self, iv: &[u8; 8], input: &[u8], output: &mut [u8]) {
+		self.cipher_cfb::<B>(iv, input, output, true)
+	}
+
+	/// Deciphers the given `&[u8]` into the output `&mut [u8]` using CFB (Cipher Feedback) mode.
+	///
+	/// See [`encipher_cfb`](XTEA::encipher_cfb) for an explanation of the mode. Decryption reuses
+	/// the same forward direction, since the feedback register is seeded from the ciphertext
+	/// either way.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// If the length of the input is not equal to the length of the output.
+	///
+	/// If the length of the input or output is not divisible by 8.
+	#[inline]
+	pub fn decipher_cfb<B: ByteOrder>(&self, iv: &[u8; 8], input: &[u8], output: &mut [u8]) {
+		self.cipher_cfb::<B>(iv, input, output, false)
+	}
+
+	#[inline]
+	fn cipher_cfb<B: ByteOrder>(&self, iv: &[u8; 8], input: &[u8], output: &mut [u8], encipher: bool) {
+		assert_eq!(input.len(), output.len(), "The input and output slices must be of the same length.");
+		assert_eq!(input.len() % 8, 0, "Input and output slices must be of a length divisible by 8.");
+
+		let mut register = *iv;
+		let mut register_buf = [0u32; 2];
+		let mut keystream_buf = [0u32; 2];
+		for (in_block, out_block) in input.chunks_exact(8).zip(output.chunks_exact_mut(8)) {
+			register_buf[0] = B::read_u32(&register[0..4]);
+			register_buf[1] = B::read_u32(&register[4..8]);
+			self.encipher(&register_buf, &mut keystream_buf);
+
+			let mut keystream = [0u8; 8];
+			B::write_u32(&mut keystream[0..4], keystream_buf[0]);
+			B::write_u32(&mut keystream[4..8], keystream_buf[1]);
+			for i in 0..8 {
+				out_block[i] = in_block[i] ^ keystream[i];
+			}
+
+			if encipher {
+				register.copy_from_slice(out_block);
+			} else {
+				register.copy_from_slice(in_block);
+			}
+		}
+	}
+
+	/// Enciphers the given `&[u8]` into the output `&mut [u8]` using OFB (Output Feedback) mode.
+	///
+	/// Like CFB, OFB only ever calls the forward [`encipher`](XTEA::encipher) on a running
+	/// feedback register and XORs the result into the data, but the register is reseeded from the
+	/// keystream itself rather than the ciphertext, making encipher and decipher identical.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// If the length of the input is not equal to the length of the output.
+	///
+	/// If the length of the input or output is not divisible by 8.
+	#[inline]
+	pub fn encipher_ofb<B: ByteOrder>(&self, iv: &[u8; 8], input: &[u8], output: &mut [u8]) {
+		self.cipher_ofb::<B>(iv, input, output)
+	}
+
+	/// Deciphers the given `&[u8]` into the output `&mut [u8]` using OFB (Output Feedback) mode.
+	///
+	/// OFB is its own inverse: see [`encipher_ofb`](XTEA::encipher_ofb) for an explanation of the
+	/// mode.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// If the length of the input is not equal to the length of the output.
+	///
+	/// If the length of the input or output is not divisible by 8.
+	#[inline]
+	pub fn decipher_ofb<B: ByteOrder>(&self, iv: &[u8; 8], input: &[u8], output: &mut [u8]) {
+		self.cipher_ofb::<B>(iv, input, output)
+	}
+
+	#[inline]
+	fn cipher_ofb<B: ByteOrder>(&self, iv: &[u8; 8], input: &[u8], output: &mut [u8]) {
+		assert_eq!(input.len(), output.len(), "The input and output slices must be of the same length.");
+		assert_eq!(input.len() % 8, 0, "Input and output slices must be of a length divisible by 8.");
+
+		let mut register = *iv;
+		let mut register_buf = [0u32; 2];
+		let mut keystream_buf = [0u32; 2];
+		for (in_block, out_block) in input.chunks_exact(8).zip(output.chunks_exact_mut(8)) {
+			register_buf[0] = B::read_u32(&register[0..4]);
+			register_buf[1] = B::read_u32(&register[4..8]);
+			self.encipher(&register_buf, &mut keystream_buf);
+
+			B::write_u32(&mut register[0..4], keystream_buf[0]);
+			B::write_u32(&mut register[4..8], keystream_buf[1]);
+			for i in 0..8 {
+				out_block[i] = in_block[i] ^ register[i];
+			}
+		}
+	}
+
 	/// Enciphers the given input stream into the given output stream.
 	///
 	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the streams.
@@ -265,13 +530,166 @@ impl XTEA {
 		}
 		Ok(())
 	}
+
+	/// Enciphers an `input` of any length by first applying PKCS#7 padding against XTEA's 8-byte
+	/// block, so callers don't need to align their input to 8 bytes themselves.
+	///
+	/// `N = 8 - (input.len() % 8)` bytes, each equal to `N`, are appended before enciphering; if
+	/// `input` is already block-aligned, a full block of `0x08` bytes is added so the padding is
+	/// always unambiguous to remove. Use [`decipher_padded`](XTEA::decipher_padded) to reverse this.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// extern crate xtea;
+	/// extern crate byteorder;
+	///
+	/// use xtea::XTEA;
+	///	use byteorder::BE;
+	///
+	/// let input = b"Hello. Performing a test here.";
+	///
+	///	let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+	///
+	/// let encrypted = xtea.encipher_padded::<BE>(input);
+	/// let decrypted = xtea.decipher_padded::<BE>(&encrypted).unwrap();
+	/// assert_eq!(input, &decrypted[..]);
+	/// ```
+	///
+	pub fn encipher_padded<B: ByteOrder>(&self, input: &[u8]) -> Vec<u8> {
+		let pad_len = 8 - (input.len() % 8);
+
+		let mut padded = Vec::with_capacity(input.len() + pad_len);
+		padded.extend_from_slice(input);
+		padded.resize(padded.len() + pad_len, pad_len as u8);
+
+		let mut output = vec![0u8; padded.len()];
+		self.encipher_u8slice::<B>(&padded, &mut output);
+		output
+	}
+
+	/// Deciphers an `input` previously enciphered with [`encipher_padded`](XTEA::encipher_padded),
+	/// stripping the PKCS#7 padding back off.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slices.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Errors
+	///
+	/// Returns [`PaddingError`] if the deciphered message's trailing padding is not a valid
+	/// PKCS#7 padding for an 8-byte block, since that means either the wrong key was used or the
+	/// ciphertext was tampered with.
+	///
+	/// # Panics
+	///
+	/// If the length of `input` is not divisible by 8.
+	pub fn decipher_padded<B: ByteOrder>(&self, input: &[u8]) -> std::result::Result<Vec<u8>, PaddingError> {
+		let mut padded = vec![0u8; input.len()];
+		self.decipher_u8slice::<B>(input, &mut padded);
+
+		if padded.len() < 8 {
+			return Err(PaddingError);
+		}
+
+		// Validated in constant time: every byte of the trailing block is inspected regardless of
+		// `pad_len`'s value, and the per-byte verdicts are combined with bitwise (not
+		// short-circuiting) operators, so the time this takes doesn't depend on how much of the
+		// padding is well-formed. Branching on that - as a naive `pad_len > 8` / `.all(...)` check
+		// would - turns malformed padding into a timing oracle on the plaintext (the classic
+		// padding-oracle attack).
+		let block = &padded[padded.len() - 8..];
+		let pad_len = block[7];
+		let mut mismatch = (pad_len == 0) as u8 | (pad_len > 8) as u8;
+		for (i, &b) in block.iter().enumerate() {
+			let distance_from_end = 8 - i as u8;
+			let mask = 0u8.wrapping_sub((distance_from_end <= pad_len) as u8);
+			mismatch |= mask & (b ^ pad_len);
+		}
+		if mismatch != 0 {
+			return Err(PaddingError);
+		}
+
+		padded.truncate(padded.len() - pad_len as usize);
+		Ok(padded)
+	}
+
+	/// Applies CTR (Counter) mode keystream to `data` in place, turning XTEA into a seekable
+	/// stream cipher.
+	///
+	/// For each 8-byte slot `i` starting at `start_block`, a `[u32; 2]` counter block is formed from
+	/// `nonce`'s upper 32 bits and `nonce`'s lower 32 bits XORed with `start_block + i`, which is
+	/// enciphered to produce a keystream block; that block is serialized to bytes via the given
+	/// [ByteOrder](https://docs.rs/byteorder) and then XORed into `data`. Because CTR is symmetric, this
+	/// single method covers both enciphering and deciphering, needs no padding for a trailing
+	/// partial block (only the keystream bytes actually used are XORed in), and the explicit
+	/// `start_block` lets callers seek into an arbitrary offset of a larger stream.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// The block counter is only 32 bits wide, so a single `nonce` can address at most 2^32
+	/// blocks (32 GiB) of keystream. Panics if `start_block` plus the number of 8-byte blocks in
+	/// `data` would exceed that, rather than silently wrapping the counter and reusing keystream.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// extern crate xtea;
+	/// extern crate byteorder;
+	///
+	/// use xtea::XTEA;
+	///	use byteorder::BE;
+	///
+	///	let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+	/// let nonce = 0xDEADBEEFCAFEu64;
+	///
+	/// let mut data = b"Hello. Performing a test here.".to_vec();
+	/// let original = data.clone();
+	///
+	/// xtea.ctr_apply::<BE>(nonce, 0, &mut data);
+	/// assert_ne!(data, original);
+	///
+	/// xtea.ctr_apply::<BE>(nonce, 0, &mut data);
+	/// assert_eq!(data, original);
+	/// ```
+	///
+	pub fn ctr_apply<B: ByteOrder>(&self, nonce: u64, start_block: u64, data: &mut [u8]) {
+		let nonce_high = (nonce >> 32) as u32;
+		let nonce_low = nonce as u32;
+
+		let mut counter_buf = [0u32; 2];
+		let mut keystream_buf = [0u32; 2];
+		let mut keystream = [0u8; 8];
+
+		for (i, chunk) in data.chunks_mut(8).enumerate() {
+			let block = start_block.wrapping_add(i as u64);
+			assert!(block <= u32::MAX as u64, "CTR mode can address at most 2^32 blocks (32 GiB) per nonce");
+			let counter = block as u32;
+			counter_buf[0] = nonce_high;
+			counter_buf[1] = nonce_low ^ counter;
+			self.encipher(&counter_buf, &mut keystream_buf);
+
+			B::write_u32(&mut keystream[0..4], keystream_buf[0]);
+			B::write_u32(&mut keystream[4..8], keystream_buf[1]);
+
+			for (b, k) in chunk.iter_mut().zip(keystream.iter()) {
+				*b ^= *k;
+			}
+		}
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use byteorder::BE;
 	use std::str;
-	use super::XTEA;
+	use super::{PaddingError, XTEA};
 
 	#[test]
 	fn overflow() {
@@ -333,4 +751,160 @@ mod tests {
 		println!("Decryted: {:?}", &decrypted);
 		assert_eq!(input, decrypted);
 	}
+
+	#[test]
+	fn cbc_roundtrip() {
+		let iv = [1, 2, 3, 4, 5, 6, 7, 8];
+		let input = b"Hello. Performing a test here.00";
+
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+
+		let encrypted = {
+			let mut output = [0; 32];
+			xtea.encipher_cbc::<BE>(&iv, input, &mut output);
+			output
+		};
+
+		let decrypted = {
+			let mut output = [0; 32];
+			xtea.decipher_cbc::<BE>(&iv, &encrypted, &mut output);
+			output
+		};
+		assert_eq!(input, &decrypted);
+	}
+
+	#[test]
+	fn cbc_hides_identical_blocks() {
+		let iv = [0u8; 8];
+		let input = [10u8; 16];
+
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+
+		let mut ecb = [0u8; 16];
+		xtea.encipher_u8slice::<BE>(&input, &mut ecb);
+		assert_eq!(ecb[0..8], ecb[8..16]);
+
+		let mut cbc = [0u8; 16];
+		xtea.encipher_cbc::<BE>(&iv, &input, &mut cbc);
+		assert_ne!(cbc[0..8], cbc[8..16]);
+	}
+
+	#[test]
+	fn cfb_roundtrip() {
+		let iv = [1, 2, 3, 4, 5, 6, 7, 8];
+		let input = b"Hello. Performing a test here.00";
+
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+
+		let encrypted = {
+			let mut output = [0; 32];
+			xtea.encipher_cfb::<BE>(&iv, input, &mut output);
+			output
+		};
+
+		let decrypted = {
+			let mut output = [0; 32];
+			xtea.decipher_cfb::<BE>(&iv, &encrypted, &mut output);
+			output
+		};
+		assert_eq!(input, &decrypted);
+	}
+
+	#[test]
+	fn ofb_roundtrip() {
+		let iv = [1, 2, 3, 4, 5, 6, 7, 8];
+		let input = b"Hello. Performing a test here.00";
+
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+
+		let encrypted = {
+			let mut output = [0; 32];
+			xtea.encipher_ofb::<BE>(&iv, input, &mut output);
+			output
+		};
+
+		let decrypted = {
+			let mut output = [0; 32];
+			xtea.decipher_ofb::<BE>(&iv, &encrypted, &mut output);
+			output
+		};
+		assert_eq!(input, &decrypted);
+	}
+
+	#[test]
+	fn padded_roundtrip_unaligned() {
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let input = b"Hello. Performing a test here.";
+
+		let encrypted = xtea.encipher_padded::<BE>(input);
+		let decrypted = xtea.decipher_padded::<BE>(&encrypted).unwrap();
+		assert_eq!(input, &decrypted[..]);
+	}
+
+	#[test]
+	fn padded_roundtrip_block_aligned() {
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let input = [10u8; 16];
+
+		let encrypted = xtea.encipher_padded::<BE>(&input);
+		// A full extra block of padding is appended even when already aligned.
+		assert_eq!(encrypted.len(), 24);
+
+		let decrypted = xtea.decipher_padded::<BE>(&encrypted).unwrap();
+		assert_eq!(&input[..], &decrypted[..]);
+	}
+
+	#[test]
+	fn padded_invalid_padding_is_rejected() {
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let mut encrypted = xtea.encipher_padded::<BE>(b"Hello.");
+
+		// Corrupt the last ciphertext block so the deciphered padding is no longer valid.
+		let len = encrypted.len();
+		encrypted[len - 1] ^= 0xFF;
+
+		assert_eq!(xtea.decipher_padded::<BE>(&encrypted), Err(PaddingError));
+	}
+
+	#[test]
+	fn ctr_roundtrip() {
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let nonce = 0xDEADBEEFCAFEu64;
+		let original = b"Hello. Performing a test here!!".to_vec();
+
+		let mut data = original.clone();
+		xtea.ctr_apply::<BE>(nonce, 0, &mut data);
+		assert_ne!(data, original);
+
+		xtea.ctr_apply::<BE>(nonce, 0, &mut data);
+		assert_eq!(data, original);
+	}
+
+	#[test]
+	fn ctr_seeking_matches_full_stream() {
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let nonce = 0xDEADBEEFCAFEu64;
+		let original = b"Hello. Performing a test here!!".to_vec();
+
+		let mut whole = original.clone();
+		xtea.ctr_apply::<BE>(nonce, 0, &mut whole);
+
+		let mut second_half = original[8..].to_vec();
+		xtea.ctr_apply::<BE>(nonce, 1, &mut second_half);
+
+		assert_eq!(&whole[8..], &second_half[..]);
+	}
+
+	#[test]
+	fn ctr_handles_partial_trailing_block() {
+		let xtea = XTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let original = b"not a multiple of eight".to_vec();
+
+		let mut data = original.clone();
+		xtea.ctr_apply::<BE>(1, 0, &mut data);
+		assert_ne!(data, original);
+
+		xtea.ctr_apply::<BE>(1, 0, &mut data);
+		assert_eq!(data, original);
+	}
 }
@@ -0,0 +1,203 @@
+/*!
+This module provides `XXTEA` (Corrected Block TEA), a sibling of [`XTEA`](crate::XTEA) that mixes
+an entire variable-length message as one block of `n >= 2` 32-bit words in a single pass, instead
+of enciphering independent 64-bit blocks. This gives diffusion across the whole message, at the
+cost of no longer being a block cipher in the usual sense - there's no notion of enciphering one
+block in isolation.
+
+See <https://en.wikipedia.org/wiki/XXTEA> for more information.
+*/
+
+use byteorder::ByteOrder;
+use std::num::Wrapping;
+
+/// Magic number specified by the algorithm; shared with plain XTEA.
+const DELTA: Wrapping<u32> = Wrapping(0x9E3779B9);
+
+/// Struct containing the `XXTEA` info.
+/// See <https://en.wikipedia.org/wiki/XXTEA> for more information
+#[derive(Debug)]
+pub struct XXTEA {
+	key: [Wrapping<u32>; 4],
+}
+
+impl XXTEA {
+	/// Creates a new `XXTEA` cipher using the given key.
+	#[inline]
+	pub fn new(key: [u32; 4]) -> Self {
+		XXTEA {
+			key: [Wrapping(key[0]), Wrapping(key[1]), Wrapping(key[2]), Wrapping(key[3])],
+		}
+	}
+
+	/// Enciphers the given `&mut [u32]` in place, treating it as a single block of `n` words.
+	///
+	/// # Panics
+	///
+	/// If `v.len()` is less than 2.
+	pub fn encipher_words(&self, v: &mut [u32]) {
+		let n = v.len();
+		assert!(n >= 2, "XXTEA operates on a message of at least 2 32-bit words.");
+
+		let rounds = 6 + 52 / n;
+		let mut sum = Wrapping(0u32);
+
+		for _ in 0..rounds {
+			sum += DELTA;
+			let e = ((sum.0 >> 2) & 3) as usize;
+
+			for i in 0..n {
+				let y = Wrapping(v[(i + 1) % n]);
+				let z = Wrapping(v[(i + n - 1) % n]);
+				let mx = (((z >> 5) ^ (y << 2)) + ((y >> 3) ^ (z << 4))) ^ ((sum ^ y) + (self.key[(i & 3) ^ e] ^ z));
+				v[i] = (Wrapping(v[i]) + mx).0;
+			}
+		}
+	}
+
+	/// Deciphers the given `&mut [u32]` in place, treating it as a single block of `n` words.
+	///
+	/// # Panics
+	///
+	/// If `v.len()` is less than 2.
+	pub fn decipher_words(&self, v: &mut [u32]) {
+		let n = v.len();
+		assert!(n >= 2, "XXTEA operates on a message of at least 2 32-bit words.");
+
+		let rounds = 6 + 52 / n;
+		let mut sum = DELTA * Wrapping(rounds as u32);
+
+		for _ in 0..rounds {
+			let e = ((sum.0 >> 2) & 3) as usize;
+
+			for i in (0..n).rev() {
+				let y = Wrapping(v[(i + 1) % n]);
+				let z = Wrapping(v[(i + n - 1) % n]);
+				let mx = (((z >> 5) ^ (y << 2)) + ((y >> 3) ^ (z << 4))) ^ ((sum ^ y) + (self.key[(i & 3) ^ e] ^ z));
+				v[i] = (Wrapping(v[i]) - mx).0;
+			}
+
+			sum -= DELTA;
+		}
+	}
+
+	/// Enciphers the given `&mut [u8]` in place.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slice.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// If the length of `data` is not divisible by 4, or is less than 8.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// extern crate xtea;
+	/// extern crate byteorder;
+	///
+	/// use xtea::xxtea::XXTEA;
+	///	use byteorder::BE;
+	///
+	/// let mut data = *b"Hello. Performing test!!";
+	///
+	///	let xxtea = XXTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+	///
+	/// xxtea.encipher_u8slice::<BE>(&mut data);
+	/// xxtea.decipher_u8slice::<BE>(&mut data);
+	/// assert_eq!(&data, b"Hello. Performing test!!");
+	/// ```
+	///
+	pub fn encipher_u8slice<B: ByteOrder>(&self, data: &mut [u8]) {
+		let mut words = words_from_bytes::<B>(data);
+		self.encipher_words(&mut words);
+		words_into_bytes::<B>(&words, data);
+	}
+
+	/// Deciphers the given `&mut [u8]` in place.
+	///
+	/// Uses the given [ByteOrder](https://docs.rs/byteorder) passed as a template for properly parsing the slice.
+	///
+	/// If you're unsure which ByteOrder to use, use `BigEndian` (BE).
+	///
+	/// # Panics
+	///
+	/// If the length of `data` is not divisible by 4, or is less than 8.
+	pub fn decipher_u8slice<B: ByteOrder>(&self, data: &mut [u8]) {
+		let mut words = words_from_bytes::<B>(data);
+		self.decipher_words(&mut words);
+		words_into_bytes::<B>(&words, data);
+	}
+}
+
+fn words_from_bytes<B: ByteOrder>(data: &[u8]) -> Vec<u32> {
+	assert_eq!(data.len() % 4, 0, "data's length must be divisible by 4.");
+	assert!(data.len() >= 8, "data must be at least 8 bytes (2 words) long.");
+	data.chunks_exact(4).map(B::read_u32).collect()
+}
+
+fn words_into_bytes<B: ByteOrder>(words: &[u32], data: &mut [u8]) {
+	for (chunk, word) in data.chunks_exact_mut(4).zip(words.iter()) {
+		B::write_u32(chunk, *word);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use byteorder::BE;
+	use super::XXTEA;
+
+	#[test]
+	fn words_roundtrip() {
+		let xxtea = XXTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let original = [1u32, 2, 3, 4, 5];
+
+		let mut words = original;
+		xxtea.encipher_words(&mut words);
+		assert_ne!(words, original);
+
+		xxtea.decipher_words(&mut words);
+		assert_eq!(words, original);
+	}
+
+	#[test]
+	fn two_word_message_roundtrip() {
+		let xxtea = XXTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let original = [1234u32, 5678u32];
+
+		let mut words = original;
+		xxtea.encipher_words(&mut words);
+		xxtea.decipher_words(&mut words);
+		assert_eq!(words, original);
+	}
+
+	#[test]
+	fn u8slice_roundtrip() {
+		let xxtea = XXTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+		let original = *b"Hello. Performing test!!";
+
+		let mut data = original;
+		xxtea.encipher_u8slice::<BE>(&mut data);
+		assert_ne!(data, original);
+
+		xxtea.decipher_u8slice::<BE>(&mut data);
+		assert_eq!(data, original);
+	}
+
+	#[test]
+	fn changes_diffuse_across_whole_message() {
+		let xxtea = XXTEA::new([0x1380C5B5, 0x28037DF9, 0x26E314A2, 0xC57684E4]);
+
+		let mut a = [0u32, 0, 0, 0, 0];
+		let mut b = [1u32, 0, 0, 0, 0];
+		xxtea.encipher_words(&mut a);
+		xxtea.encipher_words(&mut b);
+
+		// Flipping a single bit of input should change every word of a XXTEA block, unlike ECB
+		// on independent 8-byte blocks.
+		for i in 0..a.len() {
+			assert_ne!(a[i], b[i]);
+		}
+	}
+}